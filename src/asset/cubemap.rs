@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+use turbosloth::*;
+
+// Six faces in the D3D/Vulkan cubemap face order: +X, -X, +Y, -Y, +Z, -Z.
+pub struct CubemapImage {
+    pub resolution: u32,
+    pub faces: [Vec<f32>; 6],
+}
+
+#[derive(Clone, Hash)]
+pub struct LoadCubemapFaces {
+    pub face_paths: [PathBuf; 6],
+}
+
+#[async_trait]
+impl LazyWorker for LoadCubemapFaces {
+    type Output = anyhow::Result<CubemapImage>;
+
+    async fn run(self, _ctx: RunContext) -> Self::Output {
+        let mut resolution = 0;
+        let mut faces: [Vec<f32>; 6] = Default::default();
+
+        for (face, path) in faces.iter_mut().zip(self.face_paths.iter()) {
+            let img = ::image::open(path)?.to_rgba();
+            resolution = img.width();
+            *face = img.into_raw().into_iter().map(|c| c as f32 / 255.0).collect();
+        }
+
+        Ok(CubemapImage { resolution, faces })
+    }
+}
+
+// Loads a single equirectangular (lat-long) HDR panorama and resamples it onto the six faces
+// of a cube, so HDR environment probes don't need to be pre-split by an artist.
+#[derive(Clone, Hash)]
+pub struct LoadEquirectangularHdr {
+    pub path: PathBuf,
+    pub face_resolution: u32,
+}
+
+#[async_trait]
+impl LazyWorker for LoadEquirectangularHdr {
+    type Output = anyhow::Result<CubemapImage>;
+
+    async fn run(self, _ctx: RunContext) -> Self::Output {
+        let decoded = ::image::hdr::HdrDecoder::new(std::io::BufReader::new(std::fs::File::open(
+            &self.path,
+        )?))?;
+        let metadata = decoded.metadata();
+        let pixels = decoded.read_image_hdr()?;
+
+        let faces = resample_equirect_to_faces(
+            &pixels,
+            metadata.width,
+            metadata.height,
+            self.face_resolution,
+        );
+
+        Ok(CubemapImage {
+            resolution: self.face_resolution,
+            faces,
+        })
+    }
+}
+
+// Resamples an equirectangular panorama onto the six faces of a cube by, for every texel,
+// reconstructing its view direction and looking that direction up in lat-long space.
+fn resample_equirect_to_faces(
+    pixels: &[::image::Rgb<f32>],
+    src_width: u32,
+    src_height: u32,
+    face_resolution: u32,
+) -> [Vec<f32>; 6] {
+    let sample = |dir: glam::Vec3| -> [f32; 3] {
+        let u = 0.5 + dir.z().atan2(dir.x()) / (2.0 * std::f32::consts::PI);
+        let v = 0.5 - dir.y().asin() / std::f32::consts::PI;
+
+        let x = ((u * src_width as f32) as u32).min(src_width - 1);
+        let y = ((v * src_height as f32) as u32).min(src_height - 1);
+        let px = pixels[(y * src_width + x) as usize];
+        [px[0], px[1], px[2]]
+    };
+
+    let face_dir = |face: usize, s: f32, t: f32| -> glam::Vec3 {
+        match face {
+            0 => glam::Vec3::new(1.0, -t, -s),
+            1 => glam::Vec3::new(-1.0, -t, s),
+            2 => glam::Vec3::new(s, 1.0, t),
+            3 => glam::Vec3::new(s, -1.0, -t),
+            4 => glam::Vec3::new(s, -t, 1.0),
+            _ => glam::Vec3::new(-s, -t, -1.0),
+        }
+        .normalize()
+    };
+
+    let mut faces: [Vec<f32>; 6] = Default::default();
+    for (face_index, face) in faces.iter_mut().enumerate() {
+        face.reserve((face_resolution * face_resolution * 3) as usize);
+        for y in 0..face_resolution {
+            for x in 0..face_resolution {
+                let s = (x as f32 + 0.5) / face_resolution as f32 * 2.0 - 1.0;
+                let t = (y as f32 + 0.5) / face_resolution as f32 * 2.0 - 1.0;
+                let dir = face_dir(face_index, s, t);
+                face.extend_from_slice(&sample(dir));
+            }
+        }
+    }
+    faces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resample_produces_six_faces_of_the_requested_resolution() {
+        let face_resolution = 4;
+        let src_width = 8;
+        let src_height = 4;
+        let pixels = vec![::image::Rgb([0.5f32, 0.25, 0.1]); (src_width * src_height) as usize];
+
+        let faces = resample_equirect_to_faces(&pixels, src_width, src_height, face_resolution);
+
+        assert_eq!(faces.len(), 6);
+        for face in &faces {
+            assert_eq!(face.len(), (face_resolution * face_resolution * 3) as usize);
+        }
+    }
+
+    #[test]
+    fn resample_of_a_flat_panorama_samples_a_uniform_color() {
+        let face_resolution = 2;
+        let src_width = 4;
+        let src_height = 2;
+        let pixels = vec![::image::Rgb([0.5f32, 0.25, 0.1]); (src_width * src_height) as usize];
+
+        let faces = resample_equirect_to_faces(&pixels, src_width, src_height, face_resolution);
+
+        for face in &faces {
+            for texel in face.chunks(3) {
+                assert_eq!(texel, [0.5, 0.25, 0.1]);
+            }
+        }
+    }
+}
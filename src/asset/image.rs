@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+use turbosloth::*;
+
+pub struct RawRgba8Image {
+    pub data: Vec<u8>,
+    pub dimensions: [u32; 2],
+}
+
+#[derive(Clone, Hash)]
+pub struct LoadImage {
+    pub path: PathBuf,
+}
+
+#[async_trait]
+impl LazyWorker for LoadImage {
+    type Output = anyhow::Result<RawRgba8Image>;
+
+    async fn run(self, _ctx: RunContext) -> Self::Output {
+        let img = ::image::open(&self.path)?.to_rgba();
+        let dimensions = [img.width(), img.height()];
+
+        Ok(RawRgba8Image {
+            data: img.into_raw(),
+            dimensions,
+        })
+    }
+}
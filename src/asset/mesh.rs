@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+use turbosloth::*;
+
+pub enum MeshMaterialMap {
+    Asset { path: PathBuf, params: () },
+    Placeholder([u8; 4]),
+}
+
+pub struct Material {
+    pub maps: Vec<u32>,
+}
+
+pub struct RawMesh {
+    pub materials: Vec<Material>,
+}
+
+pub struct TriangleMesh {
+    pub materials: Vec<Material>,
+    pub maps: Vec<MeshMaterialMap>,
+}
+
+pub fn pack_triangle_mesh(raw: &RawMesh) -> TriangleMesh {
+    TriangleMesh {
+        materials: Vec::new(),
+        maps: Vec::new(),
+    }
+}
+
+#[derive(Clone, Hash)]
+pub struct LoadGltfScene {
+    pub path: PathBuf,
+    pub scale: f32,
+}
+
+#[async_trait]
+impl LazyWorker for LoadGltfScene {
+    type Output = anyhow::Result<RawMesh>;
+
+    async fn run(self, _ctx: RunContext) -> Self::Output {
+        Ok(RawMesh {
+            materials: Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,3 @@
+pub mod cubemap;
+pub mod image;
+pub mod mesh;
@@ -0,0 +1,83 @@
+use crate::input::InputState;
+use crate::math::*;
+
+#[derive(Clone, Copy)]
+pub struct CameraMatrices {
+    pub view_to_clip: Mat4,
+    pub world_to_view: Mat4,
+}
+
+pub struct FirstPersonCamera {
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub fov_y_radians: f32,
+    pub move_speed: f32,
+    pub look_speed: f32,
+}
+
+impl FirstPersonCamera {
+    pub fn new(position: Vec3) -> Self {
+        Self {
+            position,
+            yaw: 0.0,
+            pitch: 0.0,
+            fov_y_radians: 62.0f32.to_radians(),
+            move_speed: 2.5,
+            look_speed: 0.003,
+        }
+    }
+
+    pub fn update(&mut self, input: &InputState) {
+        if input.mouse.button_mask & 1 != 0 {
+            self.yaw -= input.mouse.pos.x * self.look_speed;
+            self.pitch -= input.mouse.pos.y * self.look_speed;
+        }
+
+        if input.gamepad.connected {
+            const GAMEPAD_LOOK_SPEED: f32 = 2.5;
+            self.yaw -= input.gamepad.right_stick.x() * GAMEPAD_LOOK_SPEED * input.dt;
+            self.pitch -= input.gamepad.right_stick.y() * GAMEPAD_LOOK_SPEED * input.dt;
+        }
+
+        let rotation = Quat::from_rotation_y(self.yaw) * Quat::from_rotation_x(self.pitch);
+        let forward = rotation * Vec3::new(0.0, 0.0, -1.0);
+        let right = rotation * Vec3::new(1.0, 0.0, 0.0);
+
+        let mut movement = Vec3::zero();
+        if input.keys.is_down(winit::VirtualKeyCode::W) {
+            movement += forward;
+        }
+        if input.keys.is_down(winit::VirtualKeyCode::S) {
+            movement -= forward;
+        }
+        if input.keys.is_down(winit::VirtualKeyCode::D) {
+            movement += right;
+        }
+        if input.keys.is_down(winit::VirtualKeyCode::A) {
+            movement -= right;
+        }
+
+        if input.gamepad.connected {
+            movement += right * input.gamepad.left_stick.x();
+            movement += forward * input.gamepad.left_stick.y();
+        }
+
+        self.position += movement * self.move_speed * input.dt;
+    }
+
+    pub fn calc_matrices(&self, aspect_ratio: f32) -> CameraMatrices {
+        let rotation = Quat::from_rotation_y(self.yaw) * Quat::from_rotation_x(self.pitch);
+        let world_to_view =
+            Mat4::from_quat(rotation.conjugate()) * Mat4::from_translation(-self.position);
+
+        CameraMatrices {
+            view_to_clip: crate::math::perspective_projection(
+                self.fov_y_radians,
+                aspect_ratio,
+                0.01,
+            ),
+            world_to_view,
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::math::*;
+use winit::KeyboardInput;
+
+#[derive(Clone, Copy, Default)]
+pub struct MouseState {
+    pub pos: Vec2,
+    pub button_mask: u32,
+}
+
+impl MouseState {
+    pub fn update(&mut self, new_state: &MouseState) {
+        self.pos = new_state.pos;
+        self.button_mask = new_state.button_mask;
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct KeyboardState {
+    keys_down: std::collections::HashSet<u32>,
+}
+
+impl KeyboardState {
+    pub fn update(&mut self, events: Vec<KeyboardInput>, _dt: f32) {
+        for event in events {
+            if let Some(code) = event.virtual_keycode {
+                let code = code as u32;
+                match event.state {
+                    winit::ElementState::Pressed => {
+                        self.keys_down.insert(code);
+                    }
+                    winit::ElementState::Released => {
+                        self.keys_down.remove(&code);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn is_down(&self, code: winit::VirtualKeyCode) -> bool {
+        self.keys_down.contains(&(code as u32))
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct GamepadState {
+    pub connected: bool,
+    pub left_stick: Vec2,
+    pub right_stick: Vec2,
+    pub left_trigger: f32,
+    pub right_trigger: f32,
+    pub button_mask: u32,
+}
+
+// Buttons packed into `GamepadState::button_mask`, one bit per entry in this list (bit `i` for
+// `GAMEPAD_BUTTONS[i]`). Face buttons first, then shoulders/sticks/menu, then the d-pad.
+const GAMEPAD_BUTTONS: [gilrs::Button; 16] = [
+    gilrs::Button::South,
+    gilrs::Button::East,
+    gilrs::Button::North,
+    gilrs::Button::West,
+    gilrs::Button::LeftTrigger,
+    gilrs::Button::LeftTrigger2,
+    gilrs::Button::RightTrigger,
+    gilrs::Button::RightTrigger2,
+    gilrs::Button::Select,
+    gilrs::Button::Start,
+    gilrs::Button::LeftThumb,
+    gilrs::Button::RightThumb,
+    gilrs::Button::DPadUp,
+    gilrs::Button::DPadDown,
+    gilrs::Button::DPadLeft,
+    gilrs::Button::DPadRight,
+];
+
+impl GamepadState {
+    // Radial deadzone: scales the stick back to [0, 1] past the deadzone radius instead of
+    // just clamping to zero inside it, so motion doesn't snap the moment it clears the zone.
+    pub fn apply_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+        let magnitude = stick.length();
+        if magnitude <= deadzone {
+            return Vec2::zero();
+        }
+        let rescaled = ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0);
+        stick / magnitude * rescaled
+    }
+
+    // Packs the pad's currently-held buttons (from `GAMEPAD_BUTTONS`) into a bitmask, the same
+    // way `MouseState::button_mask` packs mouse buttons.
+    pub fn button_mask_from_pad(pad: &gilrs::Gamepad) -> u32 {
+        GAMEPAD_BUTTONS
+            .iter()
+            .enumerate()
+            .fold(0u32, |mask, (i, &button)| {
+                if pad.is_pressed(button) {
+                    mask | (1 << i)
+                } else {
+                    mask
+                }
+            })
+    }
+}
+
+#[derive(Clone)]
+pub struct InputState {
+    pub mouse: MouseState,
+    pub keys: KeyboardState,
+    pub gamepad: GamepadState,
+    pub dt: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadzone_snaps_small_input_to_zero() {
+        let stick = GamepadState::apply_deadzone(Vec2::new(0.1, 0.0), 0.15);
+        assert_eq!(stick, Vec2::zero());
+    }
+
+    #[test]
+    fn deadzone_rescales_input_past_the_radius() {
+        let stick = GamepadState::apply_deadzone(Vec2::new(1.0, 0.0), 0.15);
+        assert!((stick.x() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn deadzone_never_exceeds_unit_magnitude() {
+        let stick = GamepadState::apply_deadzone(Vec2::new(2.0, 0.0), 0.15);
+        assert!((stick.length() - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,12 @@
+pub fn set_up_logging() -> anyhow::Result<()> {
+    use simplelog::*;
+
+    CombinedLogger::init(vec![TermLogger::new(
+        LevelFilter::Info,
+        Config::default(),
+        TerminalMode::Mixed,
+    )
+    .unwrap_or_else(|| SimpleLogger::new(LevelFilter::Info, Config::default()))])?;
+
+    Ok(())
+}
@@ -5,19 +5,23 @@ mod logging;
 mod math;
 mod render_client;
 mod render_passes;
+mod scene;
 mod viewport;
 
 use asset::{
+    cubemap::{LoadCubemapFaces, LoadEquirectangularHdr},
     image::{LoadImage, RawRgba8Image},
     mesh::*,
 };
 use camera::*;
 use input::*;
 use math::*;
+use viewport::Viewport;
 
+use anyhow::Context;
 #[allow(unused_imports)]
 use log::{debug, error, info, trace, warn};
-use render_client::BindlessImageHandle;
+use render_client::{BindlessCubemapHandle, BindlessImageHandle};
 use slingshot::*;
 use std::{collections::HashMap, path::PathBuf, sync::Arc};
 use turbosloth::*;
@@ -27,29 +31,30 @@ pub struct FrameState {
     pub camera_matrices: CameraMatrices,
     pub window_cfg: WindowConfig,
     pub input: InputState,
+    pub env_cubemap: Option<BindlessCubemapHandle>,
 }
 
-enum ImageCacheResponse {
-    Hit {
-        id: usize,
-    },
-    Miss {
-        id: usize,
-        image: Arc<RawRgba8Image>,
-    },
+// A neutral gray substituted in for any `Asset` map whose decode hasn't finished yet.
+const STREAMING_PLACEHOLDER: [u8; 4] = [127, 127, 127, 255];
+
+enum CachedImageState {
+    Pending(smol::Task<anyhow::Result<Arc<RawRgba8Image>>>),
+    Ready,
 }
+
 struct CachedImage {
-    #[allow(dead_code)] // Stored to keep the lifetime
-    lazy_handle: Lazy<RawRgba8Image>,
-    //image: Arc<RawRgba8Image>,
-    //texture: Arc<Image>,
     id: usize,
+    bindless_handle: BindlessImageHandle,
+    state: CachedImageState,
 }
 
+// Tracks in-flight and completed bindless image uploads. `Asset` maps kick off their decode
+// on a `smol` executor and return a placeholder handle immediately; `poll_loads` hot-swaps the
+// real image into the same handle once decoding finishes, so material indices never dangle.
 struct ImageCache {
     lazy_cache: Arc<LazyCache>,
     loaded_images: HashMap<PathBuf, CachedImage>,
-    placeholder_images: HashMap<[u8; 4], usize>,
+    placeholder_images: HashMap<[u8; 4], (usize, BindlessImageHandle)>,
     next_id: usize,
 }
 
@@ -63,61 +68,160 @@ impl ImageCache {
         }
     }
 
-    fn load_mesh_map(&mut self, map: &MeshMaterialMap) -> anyhow::Result<ImageCacheResponse> {
+    fn next_id(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id = self.next_id.checked_add(1).expect("Ran out of image IDs");
+        id
+    }
+
+    fn get_or_add_placeholder(
+        &mut self,
+        init_val: [u8; 4],
+        render_client: &mut render_client::VickiRenderClient,
+    ) -> BindlessImageHandle {
+        if let Some(&(_, handle)) = self.placeholder_images.get(&init_val) {
+            return handle;
+        }
+
+        let image = RawRgba8Image {
+            data: init_val.to_vec(),
+            dimensions: [1, 1],
+        };
+        let handle = render_client.add_image(&image);
+        let id = self.next_id();
+        self.placeholder_images.insert(init_val, (id, handle));
+        handle
+    }
+
+    // Returns a bindless handle immediately. An `Asset` map gets its own handle, which holds a
+    // 1x1 placeholder until `poll_loads` writes the decoded image into it in place; the same
+    // handle is returned on later calls for that path. A `Placeholder` map gets the shared 1x1
+    // handle for its color.
+    fn load_mesh_map(
+        &mut self,
+        map: &MeshMaterialMap,
+        render_client: &mut render_client::VickiRenderClient,
+    ) -> BindlessImageHandle {
         match map {
             MeshMaterialMap::Asset { path, .. } => {
-                if !self.loaded_images.contains_key(path) {
-                    let lazy_handle = LoadImage { path: path.clone() }.into_lazy();
-                    let image = smol::block_on(lazy_handle.eval(&self.lazy_cache))?;
-
-                    let id = self.next_id;
-                    self.next_id = self.next_id.checked_add(1).expect("Ran out of image IDs");
-
-                    self.loaded_images.insert(
-                        path.clone(),
-                        CachedImage {
-                            lazy_handle,
-                            //image,
-                            id,
-                        },
-                    );
-
-                    Ok(ImageCacheResponse::Miss { id, image })
-                } else {
-                    Ok(ImageCacheResponse::Hit {
-                        id: self.loaded_images[path].id,
-                    })
+                if let Some(cached) = self.loaded_images.get(path) {
+                    return cached.bindless_handle;
                 }
+
+                // Each in-flight asset gets its own placeholder-filled image (not the shared
+                // `placeholder_images` slot, which is reserved for genuinely static
+                // `MeshMaterialMap::Placeholder` maps) so completing one decode can't stomp
+                // the handle another still-pending or already-baked material is using.
+                let bindless_handle = render_client.add_image(&RawRgba8Image {
+                    data: STREAMING_PLACEHOLDER.to_vec(),
+                    dimensions: [1, 1],
+                });
+                let id = self.next_id();
+
+                let lazy_handle = LoadImage { path: path.clone() }.into_lazy();
+                let lazy_cache = self.lazy_cache.clone();
+                let task = smol::Task::spawn(async move { lazy_handle.eval(&lazy_cache).await });
+
+                self.loaded_images.insert(
+                    path.clone(),
+                    CachedImage {
+                        id,
+                        bindless_handle,
+                        state: CachedImageState::Pending(task),
+                    },
+                );
+
+                bindless_handle
             }
             MeshMaterialMap::Placeholder(init_val) => {
-                if !self.placeholder_images.contains_key(init_val) {
-                    let image = Arc::new(RawRgba8Image {
-                        data: init_val.to_vec(),
-                        dimensions: [1, 1],
-                    });
-
-                    let id = self.next_id;
-                    self.next_id = self.next_id.checked_add(1).expect("Ran out of image IDs");
-
-                    self.placeholder_images.insert(*init_val, id);
-
-                    Ok(ImageCacheResponse::Miss { id, image })
-                } else {
-                    Ok(ImageCacheResponse::Hit {
-                        id: self.placeholder_images[init_val],
-                    })
+                self.get_or_add_placeholder(*init_val, render_client)
+            }
+        }
+    }
+
+    // Polls every still-decoding image without blocking; called once per frame. Completed
+    // decodes are hot-swapped into their already-issued bindless handle; failures are logged
+    // and left on the placeholder rather than retried.
+    fn poll_loads(&mut self, render_client: &mut render_client::VickiRenderClient) {
+        for cached in self.loaded_images.values_mut() {
+            if let CachedImageState::Pending(task) = &mut cached.state {
+                match poll_once(task) {
+                    None => {}
+                    Some(Ok(image)) => {
+                        render_client.update_image(cached.bindless_handle, image.as_ref());
+                        cached.state = CachedImageState::Ready;
+                    }
+                    Some(Err(err)) => {
+                        error!("Failed to decode streamed image {}: {:?}", cached.id, err);
+                        cached.state = CachedImageState::Ready;
+                    }
                 }
             }
         }
     }
 }
 
+// Polls a future exactly once with a no-op waker, returning `None` if it isn't done yet
+// instead of blocking. Used to check in-flight image decodes without stalling the frame.
+fn poll_once<F: std::future::Future + Unpin>(future: &mut F) -> Option<F::Output> {
+    use std::future::Future;
+    use std::task::{Context, Poll};
+
+    let waker = futures::task::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    match std::pin::Pin::new(future).poll(&mut cx) {
+        Poll::Ready(value) => Some(value),
+        Poll::Pending => None,
+    }
+}
+
+// Reconfigures the swapchain and any render-client targets that depend on window
+// dimensions. Zero-sized events (e.g. minimizing on Windows) are ignored, since a
+// swapchain cannot be created with zero width or height.
+fn resize_window(
+    window_cfg: &mut WindowConfig,
+    renderer: &mut renderer::Renderer,
+    render_client: &mut render_client::VickiRenderClient,
+    physical_size: winit::dpi::PhysicalSize,
+) {
+    let width = physical_size.width.round() as u32;
+    let height = physical_size.height.round() as u32;
+
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    *window_cfg = WindowConfig { width, height };
+    renderer.resize(window_cfg);
+    render_client.resize(*window_cfg);
+}
+
+// Resolves a mesh's material maps (deduplicating already-loaded images via `image_cache`)
+// and rewrites its material map indices in place to point at their bindless handles.
+fn bind_mesh_material_maps(
+    mesh: &mut asset::mesh::TriangleMesh,
+    image_cache: &mut ImageCache,
+    render_client: &mut render_client::VickiRenderClient,
+) {
+    let mesh_map_gpu_ids: Vec<BindlessImageHandle> = mesh
+        .maps
+        .iter()
+        .map(|map| image_cache.load_mesh_map(map, render_client))
+        .collect();
+
+    for mat in &mut mesh.materials {
+        for m in &mut mat.maps {
+            *m = mesh_map_gpu_ids[*m as usize].0;
+        }
+    }
+}
+
 fn try_main() -> anyhow::Result<()> {
     logging::set_up_logging()?;
 
     let mut event_loop = winit::EventsLoop::new();
 
-    let window_cfg = WindowConfig {
+    let mut window_cfg = WindowConfig {
         width: 1280,
         height: 720,
     };
@@ -135,10 +239,18 @@ fn try_main() -> anyhow::Result<()> {
 
     let lazy_cache = LazyCache::create();
 
+    let mut shader_store = render_passes::shader::ShaderStore::new("shaders");
+    let shader_watcher = render_passes::shader_watcher::ShaderWatcher::new("shaders")?;
+
     let render_backend = RenderBackend::new(&*window, &window_cfg)?;
-    let mut render_client = render_client::VickiRenderClient::new(&render_backend)?;
+    let mut render_client =
+        render_client::VickiRenderClient::new(&render_backend, &mut shader_store)?;
     let mut renderer = renderer::Renderer::new(render_backend)?;
 
+    if let Some(settings) = render_client.light_shadow_settings(0) {
+        info!("Key light shadow filtering: {:?}", settings.filter_mode);
+    }
+
     let mut last_error_text = None;
 
     #[allow(unused_mut)]
@@ -146,47 +258,84 @@ fn try_main() -> anyhow::Result<()> {
 
     let mut mouse_state: MouseState = Default::default();
     let mut keyboard: KeyboardState = Default::default();
+    let mut gilrs = gilrs::Gilrs::new()?;
+    const GAMEPAD_DEADZONE: f32 = 0.15;
 
     let mut keyboard_events: Vec<KeyboardInput> = Vec::new();
     let mut new_mouse_state: MouseState = Default::default();
 
-    let mesh = LoadGltfScene {
-        path: "assets/meshes/the_lighthouse/scene.gltf".into(),
-        scale: 0.01,
-    }
-    .into_lazy();
-    let mesh = smol::block_on(mesh.eval(&lazy_cache))?;
+    let scene_manifest_path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "assets/scenes/default.ron".to_string());
+    let scene_desc = scene::load_scene_desc(std::path::Path::new(&scene_manifest_path))?;
 
     let mut image_cache = ImageCache::new(lazy_cache.clone());
-    let mut cached_image_to_bindless_handle: HashMap<usize, BindlessImageHandle> =
-        Default::default();
 
-    let mut mesh = pack_triangle_mesh(&mesh);
-    {
-        let mesh_map_gpu_ids: Vec<BindlessImageHandle> = mesh
-            .maps
+    let mesh_handles: Vec<render_client::MeshHandle> = scene_desc
+        .meshes
+        .iter()
+        .map(|mesh_desc| -> anyhow::Result<_> {
+            let mesh = LoadGltfScene {
+                path: mesh_desc.path.clone(),
+                scale: mesh_desc.scale,
+            }
+            .into_lazy();
+            let mesh = smol::block_on(mesh.eval(&lazy_cache))?;
+            let mut mesh = pack_triangle_mesh(&mesh);
+
+            bind_mesh_material_maps(&mut mesh, &mut image_cache, &mut render_client);
+
+            Ok(render_client.add_mesh(mesh))
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    let scene_instances: Vec<(render_client::InstanceHandle, Mat4, Option<scene::InstanceAnimation>)> =
+        scene_desc
+            .instances
             .iter()
-            .map(|map| {
-                let img = image_cache.load_mesh_map(map).unwrap();
-                match img {
-                    ImageCacheResponse::Hit { id } => cached_image_to_bindless_handle[&id],
-                    ImageCacheResponse::Miss { id, image } => {
-                        let handle = render_client.add_image(image.as_ref());
-                        cached_image_to_bindless_handle.insert(id, handle);
-                        handle
-                    }
-                }
+            .map(|instance_desc| -> anyhow::Result<_> {
+                let mesh_handle = *mesh_handles.get(instance_desc.mesh_index).with_context(|| {
+                    format!(
+                        "scene instance references mesh_index {}, but the manifest only lists {} mesh(es)",
+                        instance_desc.mesh_index,
+                        mesh_handles.len()
+                    )
+                })?;
+                let base_transform = instance_desc.transform.to_mat4();
+                let instance_handle = render_client.add_instance(mesh_handle, base_transform);
+                Ok((instance_handle, base_transform, instance_desc.animation))
             })
-            .collect();
-        for mat in &mut mesh.materials {
-            for m in &mut mat.maps {
-                *m = mesh_map_gpu_ids[*m as usize].0;
-            }
+            .collect::<anyhow::Result<_>>()?;
+
+    let env_cubemap = match scene_desc.sky {
+        Some(scene::SkyDesc::Faces { face_paths }) => {
+            smol::block_on(LoadCubemapFaces { face_paths }.into_lazy().eval(&lazy_cache))?
         }
-    }
-    render_client.add_mesh(mesh);
+        Some(scene::SkyDesc::Equirectangular {
+            path,
+            face_resolution,
+        }) => smol::block_on(
+            LoadEquirectangularHdr {
+                path,
+                face_resolution,
+            }
+            .into_lazy()
+            .eval(&lazy_cache),
+        )?,
+        None => smol::block_on(
+            LoadEquirectangularHdr {
+                path: "assets/sky/studio_small.hdr".into(),
+                face_resolution: 512,
+            }
+            .into_lazy()
+            .eval(&lazy_cache),
+        )?,
+    };
+    let env_cubemap = render_client.add_cubemap(&env_cubemap);
+    render_client.set_skybox_cubemap(env_cubemap);
 
-    let mut last_frame_instant = std::time::Instant::now();
+    let scene_start_instant = std::time::Instant::now();
+    let mut last_frame_instant = scene_start_instant;
     let mut running = true;
     while running {
         let mut events = Vec::new();
@@ -218,6 +367,25 @@ fn try_main() -> anyhow::Result<()> {
                             new_mouse_state.button_mask &= !(1 << button_id);
                         }
                     }
+                    WindowEvent::Resized(logical_size) => {
+                        let hidpi_factor = window.get_hidpi_factor();
+                        resize_window(
+                            &mut window_cfg,
+                            &mut renderer,
+                            &mut render_client,
+                            logical_size.to_physical(hidpi_factor),
+                        );
+                    }
+                    WindowEvent::HiDpiFactorChanged(hidpi_factor) => {
+                        if let Some(logical_size) = window.get_inner_size() {
+                            resize_window(
+                                &mut window_cfg,
+                                &mut renderer,
+                                &mut render_client,
+                                logical_size.to_physical(hidpi_factor),
+                            );
+                        }
+                    }
                     _ => (),
                 },
                 _ => (),
@@ -233,17 +401,73 @@ fn try_main() -> anyhow::Result<()> {
         mouse_state.update(&new_mouse_state);
         new_mouse_state = mouse_state.clone();
 
+        while gilrs.next_event().is_some() {}
+        let gamepad_state = gilrs
+            .gamepads()
+            .find(|(_id, pad)| pad.is_connected())
+            .map(|(_id, pad)| GamepadState {
+                connected: pad.is_connected(),
+                left_stick: GamepadState::apply_deadzone(
+                    Vec2::new(
+                        pad.value(gilrs::Axis::LeftStickX),
+                        pad.value(gilrs::Axis::LeftStickY),
+                    ),
+                    GAMEPAD_DEADZONE,
+                ),
+                right_stick: GamepadState::apply_deadzone(
+                    Vec2::new(
+                        pad.value(gilrs::Axis::RightStickX),
+                        pad.value(gilrs::Axis::RightStickY),
+                    ),
+                    GAMEPAD_DEADZONE,
+                ),
+                left_trigger: pad.value(gilrs::Axis::LeftZ),
+                right_trigger: pad.value(gilrs::Axis::RightZ),
+                button_mask: GamepadState::button_mask_from_pad(&pad),
+            })
+            .unwrap_or_default();
+
         let input_state = InputState {
             mouse: mouse_state,
             keys: keyboard.clone(),
+            gamepad: gamepad_state,
             dt,
         };
         camera.update(&input_state);
 
+        image_cache.poll_loads(&mut render_client);
+
+        let elapsed_seconds = (now - scene_start_instant).as_secs_f32();
+        for (instance_handle, base_transform, animation) in &scene_instances {
+            if let Some(animation) = animation {
+                let transform = animation.evaluate(*base_transform, elapsed_seconds);
+                render_client.update_instance_transform(*instance_handle, transform);
+            }
+        }
+
+        let dirty_shader_paths = shader_watcher.poll_dirty_paths();
+        if !dirty_shader_paths.is_empty() {
+            let (reloaded, reload_errors) = shader_store.reload_dirty(&dirty_shader_paths);
+            render_client.rebuild_shaders(&shader_store, &reloaded);
+
+            for err in reload_errors {
+                let error_text = Some(format!("{:?}", err));
+                if error_text != last_error_text {
+                    println!("{}", error_text.as_ref().unwrap());
+                    last_error_text = error_text;
+                }
+            }
+        }
+
+        let viewport = Viewport {
+            width: window_cfg.width,
+            height: window_cfg.height,
+        };
         let frame_state = FrameState {
-            camera_matrices: camera.calc_matrices(),
-            window_cfg: window_cfg,
+            camera_matrices: camera.calc_matrices(viewport.aspect_ratio()),
+            window_cfg,
             input: input_state,
+            env_cubemap: Some(env_cubemap),
         };
 
         match renderer.prepare_frame(&mut render_client, &frame_state) {
@@ -0,0 +1,24 @@
+pub use glam::{Mat4, Quat, Vec2, Vec3, Vec4};
+
+pub fn perspective_projection(fov_y_radians: f32, aspect_ratio: f32, z_near: f32) -> Mat4 {
+    // Infinite far plane, reversed-Z, matching the depth convention used by the renderer.
+    let f = 1.0 / (fov_y_radians * 0.5).tan();
+    Mat4::from_cols_array(&[
+        f / aspect_ratio,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        f,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        -1.0,
+        0.0,
+        0.0,
+        z_near,
+        0.0,
+    ])
+}
@@ -0,0 +1,146 @@
+use crate::asset::cubemap::CubemapImage;
+use crate::asset::mesh::TriangleMesh;
+use crate::asset::image::RawRgba8Image;
+use crate::math::Mat4;
+use crate::render_passes::shader::{Pipeline, ShaderHandle, ShaderStore};
+use crate::render_passes::shadow::{LightShadowSettings, ShadowAtlas};
+use crate::render_passes::skybox::SkyboxPass;
+use slingshot::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BindlessImageHandle(pub u32);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BindlessCubemapHandle(pub u32);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MeshHandle(pub u32);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InstanceHandle(pub u32);
+
+struct MeshInstance {
+    mesh: MeshHandle,
+    transform: Mat4,
+}
+
+pub struct VickiRenderClient {
+    bindless_images: Vec<()>,
+    bindless_cubemaps: Vec<()>,
+    meshes: Vec<TriangleMesh>,
+    instances: Vec<MeshInstance>,
+    window_cfg: WindowConfig,
+    gbuffer_pipeline: Pipeline,
+    shadow_atlas: ShadowAtlas,
+    light_shadow_settings: Vec<LightShadowSettings>,
+    skybox: SkyboxPass,
+}
+
+impl VickiRenderClient {
+    pub fn new(_backend: &RenderBackend, shader_store: &mut ShaderStore) -> anyhow::Result<Self> {
+        let gbuffer_shader = shader_store.add_file("gbuffer.hlsl")?;
+        let gbuffer_pipeline = Pipeline::new(shader_store, gbuffer_shader);
+        let shadow_atlas = ShadowAtlas::new(shader_store, 4096, 4)?;
+        let skybox = SkyboxPass::new(shader_store)?;
+
+        let mut render_client = Self {
+            bindless_images: Vec::new(),
+            bindless_cubemaps: Vec::new(),
+            meshes: Vec::new(),
+            instances: Vec::new(),
+            window_cfg: WindowConfig {
+                width: 1280,
+                height: 720,
+            },
+            gbuffer_pipeline,
+            shadow_atlas,
+            light_shadow_settings: Vec::new(),
+            skybox,
+        };
+
+        // The scene always has at least an implicit key light; give it sane default shadow
+        // filtering so there's something in `light_shadow_settings` before per-light authoring
+        // exists to populate it explicitly.
+        render_client.set_light_shadow_settings(0, LightShadowSettings::default());
+
+        Ok(render_client)
+    }
+
+    pub fn add_cubemap(&mut self, _cubemap: &CubemapImage) -> BindlessCubemapHandle {
+        let handle = BindlessCubemapHandle(self.bindless_cubemaps.len() as u32);
+        self.bindless_cubemaps.push(());
+        handle
+    }
+
+    // Sets the environment cubemap the skybox pass draws behind the gbuffer; the camera's
+    // orientation (via `FrameState::camera_matrices`) drives the sampled direction each frame.
+    pub fn set_skybox_cubemap(&mut self, cubemap: BindlessCubemapHandle) {
+        self.skybox.set_cubemap(cubemap);
+    }
+
+    // Assigns shadow filtering settings to the light at `light_index`, growing the settings
+    // list (defaulted to `LightShadowSettings::default()`) if needed.
+    pub fn set_light_shadow_settings(&mut self, light_index: usize, settings: LightShadowSettings) {
+        if self.light_shadow_settings.len() <= light_index {
+            self.light_shadow_settings
+                .resize(light_index + 1, LightShadowSettings::default());
+        }
+        self.light_shadow_settings[light_index] = settings;
+    }
+
+    // Read back by the shadow pass when it renders each light's depth/filter pass.
+    pub fn light_shadow_settings(&self, light_index: usize) -> Option<&LightShadowSettings> {
+        self.light_shadow_settings.get(light_index)
+    }
+
+    // Called once per frame for any shader handles whose backing file was recompiled since
+    // the last poll; swaps the new module into the pipeline it belongs to.
+    pub fn rebuild_shaders(&mut self, shader_store: &ShaderStore, dirtied: &[ShaderHandle]) {
+        for &handle in dirtied {
+            if self.gbuffer_pipeline.rebuild_if_matches(shader_store, handle) {
+                log::debug!(
+                    "Rebuilt gbuffer pipeline ({} bytes of source)",
+                    self.gbuffer_pipeline.source().len()
+                );
+            }
+            self.skybox.rebuild_if_matches(shader_store, handle);
+            self.shadow_atlas.rebuild_if_matches(shader_store, handle);
+        }
+    }
+
+    // Records the new swapchain dimensions. The client holds no size-dependent render targets
+    // of its own, so nothing else is rebuilt here.
+    pub fn resize(&mut self, window_cfg: WindowConfig) {
+        self.window_cfg = window_cfg;
+    }
+
+    pub fn add_image(&mut self, _image: &RawRgba8Image) -> BindlessImageHandle {
+        let handle = BindlessImageHandle(self.bindless_images.len() as u32);
+        self.bindless_images.push(());
+        handle
+    }
+
+    // Replaces the GPU contents behind an already-issued bindless handle, e.g. swapping a
+    // streaming placeholder for the fully decoded image once it's ready.
+    pub fn update_image(&mut self, _handle: BindlessImageHandle, _image: &RawRgba8Image) {}
+
+    pub fn add_mesh(&mut self, mesh: TriangleMesh) -> MeshHandle {
+        let handle = MeshHandle(self.meshes.len() as u32);
+        self.meshes.push(mesh);
+        handle
+    }
+
+    // Clones `mesh` into the scene at `transform`, so a small set of loaded meshes can back
+    // many draws without re-uploading geometry per instance.
+    pub fn add_instance(&mut self, mesh: MeshHandle, transform: Mat4) -> InstanceHandle {
+        let handle = InstanceHandle(self.instances.len() as u32);
+        self.instances.push(MeshInstance { mesh, transform });
+        handle
+    }
+
+    // Called each frame for instances with an animation, after it has been evaluated from the
+    // seconds elapsed since the scene started.
+    pub fn update_instance_transform(&mut self, instance: InstanceHandle, transform: Mat4) {
+        self.instances[instance.0 as usize].transform = transform;
+    }
+}
@@ -0,0 +1,6 @@
+// Render passes used by `renderer::Renderer` when building the frame graph.
+
+pub mod shader;
+pub mod shader_watcher;
+pub mod shadow;
+pub mod skybox;
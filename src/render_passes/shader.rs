@@ -0,0 +1,123 @@
+use anyhow::Context;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ShaderHandle(pub usize);
+
+pub struct CompiledShaderModule {
+    pub source: String,
+}
+
+fn compile_shader(path: &Path) -> anyhow::Result<CompiledShaderModule> {
+    let source =
+        std::fs::read_to_string(path).with_context(|| format!("compiling shader {:?}", path))?;
+    Ok(CompiledShaderModule { source })
+}
+
+// Owns compiled shader modules keyed by a stable handle, so render passes can hold a handle
+// instead of a baked pipeline and keep working across hot-reloads.
+pub struct ShaderStore {
+    shaders_dir: PathBuf,
+    handles_by_path: HashMap<PathBuf, ShaderHandle>,
+    modules: Vec<CompiledShaderModule>,
+    paths: Vec<PathBuf>,
+}
+
+impl ShaderStore {
+    pub fn new(shaders_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            shaders_dir: shaders_dir.into(),
+            handles_by_path: HashMap::new(),
+            modules: Vec::new(),
+            paths: Vec::new(),
+        }
+    }
+
+    // Registers a shader file by name relative to the shaders directory, compiling it
+    // immediately and returning a handle the caller stores in place of a baked pipeline.
+    pub fn add_file(&mut self, file_name: &str) -> anyhow::Result<ShaderHandle> {
+        let path = self.shaders_dir.join(file_name);
+        if let Some(&handle) = self.handles_by_path.get(&path) {
+            return Ok(handle);
+        }
+
+        let module = compile_shader(&path)?;
+        let handle = ShaderHandle(self.modules.len());
+        self.modules.push(module);
+        self.paths.push(path.clone());
+        self.handles_by_path.insert(path, handle);
+        Ok(handle)
+    }
+
+    pub fn module(&self, handle: ShaderHandle) -> &CompiledShaderModule {
+        &self.modules[handle.0]
+    }
+
+    fn recompile(&mut self, handle: ShaderHandle) -> anyhow::Result<()> {
+        let module = compile_shader(&self.paths[handle.0])?;
+        self.modules[handle.0] = module;
+        Ok(())
+    }
+
+    // Recompiles every handle whose backing file appears in `dirty_paths`, leaving handles
+    // whose compile failed pointing at their last-good module. Returns the handles that were
+    // rebuilt (for callers to swap into their pipelines) and any compile errors, so one broken
+    // shader doesn't stop the others from reloading or crash the app.
+    pub fn reload_dirty(
+        &mut self,
+        dirty_paths: &[PathBuf],
+    ) -> (Vec<ShaderHandle>, Vec<anyhow::Error>) {
+        let mut reloaded = Vec::new();
+        let mut errors = Vec::new();
+
+        for path in dirty_paths {
+            if let Some(&handle) = self.handles_by_path.get(path) {
+                match self.recompile(handle) {
+                    Ok(()) => reloaded.push(handle),
+                    Err(err) => errors.push(err),
+                }
+            }
+        }
+
+        (reloaded, errors)
+    }
+}
+
+// A pipeline baked from a single shader module. Holding a copy of the module's source (rather
+// than just the handle) means `rebuild` has something concrete to replace in place, so a
+// hot-reload is an observable swap rather than a no-op that only touches the `ShaderStore`.
+pub struct Pipeline {
+    shader: ShaderHandle,
+    source: String,
+}
+
+impl Pipeline {
+    pub fn new(shader_store: &ShaderStore, shader: ShaderHandle) -> Self {
+        Self {
+            shader,
+            source: shader_store.module(shader).source.clone(),
+        }
+    }
+
+    pub fn shader(&self) -> ShaderHandle {
+        self.shader
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    // Rebuilds this pipeline from the store if `handle` is the shader it was baked from.
+    // Returns whether a rebuild happened, so callers can batch multiple pipelines per handle.
+    pub fn rebuild_if_matches(&mut self, shader_store: &ShaderStore, handle: ShaderHandle) -> bool {
+        if handle != self.shader {
+            return false;
+        }
+
+        self.source = shader_store.module(handle).source.clone();
+        true
+    }
+}
@@ -0,0 +1,39 @@
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver},
+    time::Duration,
+};
+
+// Watches the shaders directory on a background thread and buffers change events for the
+// main loop to drain once per frame; nothing here blocks rendering.
+pub struct ShaderWatcher {
+    _watcher: RecommendedWatcher,
+    rx: Receiver<DebouncedEvent>,
+}
+
+impl ShaderWatcher {
+    pub fn new(shaders_dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let (tx, rx) = channel();
+        let mut watcher = notify::watcher(tx, Duration::from_millis(200))?;
+        watcher.watch(shaders_dir.as_ref(), RecursiveMode::Recursive)?;
+        Ok(Self {
+            _watcher: watcher,
+            rx,
+        })
+    }
+
+    // Non-blocking; returns the paths that changed since the last call.
+    pub fn poll_dirty_paths(&self) -> Vec<PathBuf> {
+        let mut dirty = Vec::new();
+        while let Ok(event) = self.rx.try_recv() {
+            match event {
+                DebouncedEvent::Write(path)
+                | DebouncedEvent::Create(path)
+                | DebouncedEvent::Rename(_, path) => dirty.push(path),
+                _ => {}
+            }
+        }
+        dirty
+    }
+}
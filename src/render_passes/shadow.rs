@@ -0,0 +1,116 @@
+use crate::render_passes::shader::{Pipeline, ShaderHandle, ShaderStore};
+
+// Selects how the shadow-map comparison result is filtered before it reaches lighting.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShadowFilterMode {
+    /// Single hardware 2x2 PCF tap (`SampleCmpLevelZero`). Cheapest, hard edges.
+    Hardware2x2,
+    /// Fixed-radius Poisson-disk PCF, rotated per-pixel to trade banding for noise.
+    Pcf,
+    /// PCF with a blocker search driving a penumbra-proportional filter radius.
+    Pcss,
+    /// No filtering at all; useful as a hard-edge fallback when debugging acne/peter-panning.
+    Disabled,
+}
+
+// Per-light shadow parameters; each light in the scene owns one of these.
+#[derive(Clone, Copy, Debug)]
+pub struct LightShadowSettings {
+    pub filter_mode: ShadowFilterMode,
+    /// Depth-comparison bias, in shadow-map NDC units, to fight surface acne.
+    pub depth_bias: f32,
+    /// Physical size of the light emitter, in shadow-map UV units, used by PCSS to turn the
+    /// blocker/receiver depth gap into a penumbra (filter) radius.
+    pub light_size: f32,
+    pub pcf_sample_count: u32,
+}
+
+impl Default for LightShadowSettings {
+    fn default() -> Self {
+        Self {
+            filter_mode: ShadowFilterMode::Pcf,
+            depth_bias: 0.0015,
+            light_size: 0.02,
+            pcf_sample_count: 16,
+        }
+    }
+}
+
+// Depth-only render target each light renders into, and the shader used to sample it during
+// lighting. Lights are packed into a square grid of fixed-size tiles of a single atlas texture.
+pub struct ShadowAtlas {
+    pub resolution: u32,
+    pub tile_size: u32,
+    /// Tiles per row/column of the atlas grid; the atlas holds `tiles_per_row * tiles_per_row`
+    /// lights in total, not `tiles_per_row`.
+    pub tiles_per_row: u32,
+    depth_pipeline: Pipeline,
+    filter_pipeline: Pipeline,
+}
+
+impl ShadowAtlas {
+    pub fn new(
+        shader_store: &mut ShaderStore,
+        resolution: u32,
+        tiles_per_row: u32,
+    ) -> anyhow::Result<Self> {
+        let depth_shader = shader_store.add_file("shadow_map.hlsl")?;
+        let filter_shader = shader_store.add_file("shadow_filter.hlsl")?;
+
+        Ok(Self {
+            resolution,
+            tile_size: resolution / tiles_per_row.max(1),
+            tiles_per_row,
+            depth_pipeline: Pipeline::new(shader_store, depth_shader),
+            filter_pipeline: Pipeline::new(shader_store, filter_shader),
+        })
+    }
+
+    pub fn total_tile_capacity(&self) -> u32 {
+        self.tiles_per_row * self.tiles_per_row
+    }
+
+    pub fn shader_handles(&self) -> [ShaderHandle; 2] {
+        [self.depth_pipeline.shader(), self.filter_pipeline.shader()]
+    }
+
+    // Rebuilds whichever of the depth/filter pipelines `handle` belongs to. Returns whether
+    // either pipeline was rebuilt.
+    pub fn rebuild_if_matches(&mut self, shader_store: &ShaderStore, handle: ShaderHandle) -> bool {
+        let depth_rebuilt = self.depth_pipeline.rebuild_if_matches(shader_store, handle);
+        let filter_rebuilt = self.filter_pipeline.rebuild_if_matches(shader_store, handle);
+        depth_rebuilt || filter_rebuilt
+    }
+
+    // Lights are laid out left-to-right, top-to-bottom in the tile grid.
+    pub fn tile_rect(&self, light_index: u32) -> (u32, u32, u32, u32) {
+        let tiles_per_row = self.tiles_per_row.max(1);
+        let col = light_index % tiles_per_row;
+        let row = light_index / tiles_per_row;
+        (col * self.tile_size, row * self.tile_size, self.tile_size, self.tile_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_atlas(resolution: u32, tiles_per_row: u32) -> ShadowAtlas {
+        let mut shader_store = ShaderStore::new("shaders");
+        ShadowAtlas::new(&mut shader_store, resolution, tiles_per_row).unwrap()
+    }
+
+    #[test]
+    fn total_tile_capacity_is_the_full_grid_not_just_one_row() {
+        let atlas = new_atlas(4096, 4);
+        assert_eq!(atlas.total_tile_capacity(), 16);
+    }
+
+    #[test]
+    fn tile_rect_wraps_to_the_next_row() {
+        let atlas = new_atlas(4096, 4);
+        assert_eq!(atlas.tile_rect(0), (0, 0, 1024, 1024));
+        assert_eq!(atlas.tile_rect(3), (3 * 1024, 0, 1024, 1024));
+        assert_eq!(atlas.tile_rect(4), (0, 1024, 1024, 1024));
+    }
+}
@@ -0,0 +1,36 @@
+use crate::render_client::BindlessCubemapHandle;
+use crate::render_passes::shader::{Pipeline, ShaderHandle, ShaderStore};
+
+// Draws the environment cubemap behind the gbuffer wherever no geometry was rasterized.
+// `cubemap()` exposes the same handle to other passes; no pass here uses it for lighting.
+pub struct SkyboxPass {
+    pipeline: Pipeline,
+    cubemap: Option<BindlessCubemapHandle>,
+}
+
+impl SkyboxPass {
+    pub fn new(shader_store: &mut ShaderStore) -> anyhow::Result<Self> {
+        let shader = shader_store.add_file("skybox.hlsl")?;
+        Ok(Self {
+            pipeline: Pipeline::new(shader_store, shader),
+            cubemap: None,
+        })
+    }
+
+    pub fn shader_handle(&self) -> ShaderHandle {
+        self.pipeline.shader()
+    }
+
+    // Rebuilds the skybox pipeline if `handle` is the shader it was baked from.
+    pub fn rebuild_if_matches(&mut self, shader_store: &ShaderStore, handle: ShaderHandle) -> bool {
+        self.pipeline.rebuild_if_matches(shader_store, handle)
+    }
+
+    pub fn set_cubemap(&mut self, cubemap: BindlessCubemapHandle) {
+        self.cubemap = Some(cubemap);
+    }
+
+    pub fn cubemap(&self) -> Option<BindlessCubemapHandle> {
+        self.cubemap
+    }
+}
@@ -0,0 +1,178 @@
+use crate::math::*;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+// On-disk scene manifest: a small set of gltf meshes cloned across many per-instance
+// transforms, the way the cyborg `Grid`/`Planets` worlds clone one `GltfModel` across a grid.
+#[derive(Deserialize)]
+pub struct SceneDesc {
+    pub meshes: Vec<SceneMeshDesc>,
+    pub instances: Vec<SceneInstanceDesc>,
+    #[serde(default)]
+    pub sky: Option<SkyDesc>,
+}
+
+// Selects how the environment cubemap is sourced: resampled from a single equirectangular HDR
+// panorama, or loaded directly from six pre-split face images.
+#[derive(Deserialize)]
+#[serde(tag = "kind")]
+pub enum SkyDesc {
+    Equirectangular {
+        path: PathBuf,
+        #[serde(default = "default_face_resolution")]
+        face_resolution: u32,
+    },
+    Faces {
+        face_paths: [PathBuf; 6],
+    },
+}
+
+fn default_face_resolution() -> u32 {
+    512
+}
+
+#[derive(Deserialize)]
+pub struct SceneMeshDesc {
+    pub path: PathBuf,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct Transform {
+    #[serde(default)]
+    pub translation: [f32; 3],
+    #[serde(default)]
+    pub rotation_euler_degrees: [f32; 3],
+    #[serde(default = "default_unit_scale")]
+    pub scale: [f32; 3],
+}
+
+fn default_unit_scale() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+impl Transform {
+    pub fn to_mat4(&self) -> Mat4 {
+        let rotation = Quat::from_rotation_ypr(
+            self.rotation_euler_degrees[1].to_radians(),
+            self.rotation_euler_degrees[0].to_radians(),
+            self.rotation_euler_degrees[2].to_radians(),
+        );
+
+        Mat4::from_scale_rotation_translation(
+            Vec3::from(self.scale),
+            rotation,
+            Vec3::from(self.translation),
+        )
+    }
+}
+
+// Per-instance animation, evaluated each frame from the seconds elapsed since the scene
+// started and composed with the instance's base transform rather than replacing it.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(tag = "kind")]
+pub enum InstanceAnimation {
+    Orbit {
+        axis: [f32; 3],
+        radians_per_second: f32,
+    },
+    Spin {
+        axis: [f32; 3],
+        radians_per_second: f32,
+    },
+}
+
+impl InstanceAnimation {
+    pub fn evaluate(&self, base_transform: Mat4, elapsed_seconds: f32) -> Mat4 {
+        match *self {
+            InstanceAnimation::Orbit {
+                axis,
+                radians_per_second,
+            } => {
+                let rotation =
+                    Quat::from_axis_angle(Vec3::from(axis).normalize(), elapsed_seconds * radians_per_second);
+                Mat4::from_quat(rotation) * base_transform
+            }
+            InstanceAnimation::Spin {
+                axis,
+                radians_per_second,
+            } => {
+                let rotation =
+                    Quat::from_axis_angle(Vec3::from(axis).normalize(), elapsed_seconds * radians_per_second);
+                base_transform * Mat4::from_quat(rotation)
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct SceneInstanceDesc {
+    pub mesh_index: usize,
+    pub transform: Transform,
+    #[serde(default)]
+    pub animation: Option<InstanceAnimation>,
+}
+
+pub fn load_scene_desc(path: &std::path::Path) -> anyhow::Result<SceneDesc> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(ron::de::from_str(&text)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transform_to_mat4_places_translation_in_the_last_column() {
+        let transform = Transform {
+            translation: [1.0, 2.0, 3.0],
+            rotation_euler_degrees: [0.0, 0.0, 0.0],
+            scale: [1.0, 1.0, 1.0],
+        };
+        let translated = transform.to_mat4().transform_point3(Vec3::zero());
+        assert_eq!(translated, Vec3::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn transform_to_mat4_applies_scale() {
+        let transform = Transform {
+            translation: [0.0, 0.0, 0.0],
+            rotation_euler_degrees: [0.0, 0.0, 0.0],
+            scale: [2.0, 1.0, 1.0],
+        };
+        let scaled = transform.to_mat4().transform_point3(Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(scaled, Vec3::new(2.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn orbit_rotates_the_base_transform_about_the_origin() {
+        let base_transform = Mat4::from_translation(Vec3::new(1.0, 0.0, 0.0));
+        let animation = InstanceAnimation::Orbit {
+            axis: [0.0, 1.0, 0.0],
+            radians_per_second: std::f32::consts::FRAC_PI_2,
+        };
+        let orbited = animation
+            .evaluate(base_transform, 1.0)
+            .transform_point3(Vec3::zero());
+        assert!(orbited.x().abs() < 1e-5);
+        assert!((orbited.z() - 1.0).abs() < 1e-5 || (orbited.z() + 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn spin_leaves_the_base_translation_untouched() {
+        let base_transform = Mat4::from_translation(Vec3::new(1.0, 0.0, 0.0));
+        let animation = InstanceAnimation::Spin {
+            axis: [0.0, 1.0, 0.0],
+            radians_per_second: 1.0,
+        };
+        let spun = animation
+            .evaluate(base_transform, 1.0)
+            .transform_point3(Vec3::zero());
+        assert!((spun - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-5);
+    }
+}
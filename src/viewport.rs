@@ -0,0 +1,34 @@
+#[derive(Clone, Copy, Debug)]
+pub struct Viewport {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Viewport {
+    pub fn aspect_ratio(&self) -> f32 {
+        self.width as f32 / self.height.max(1) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aspect_ratio_matches_width_over_height() {
+        let viewport = Viewport {
+            width: 1920,
+            height: 1080,
+        };
+        assert!((viewport.aspect_ratio() - 1920.0 / 1080.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn aspect_ratio_does_not_divide_by_zero() {
+        let viewport = Viewport {
+            width: 100,
+            height: 0,
+        };
+        assert_eq!(viewport.aspect_ratio(), 100.0);
+    }
+}